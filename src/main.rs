@@ -2,6 +2,7 @@ use anyhow::Result;
 use glob::glob;
 use imara_diff::intern::InternedInput;
 use imara_diff::{diff, Algorithm, UnifiedDiffBuilder};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use slug::slugify;
 use std::collections::{HashMap, HashSet};
@@ -15,6 +16,54 @@ use thiserror::Error;
 
 const MAX_LINE_LENGTH: usize = 80;
 
+/// Output format for `Validate` and `Sort`: human-readable prose, or a
+/// machine-readable JSON report for CI annotation tooling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Human,
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(OutputFormat::Human),
+            "json" => Ok(OutputFormat::Json),
+            other => Err(format!(
+                "invalid format `{}`, expected `human` or `json`",
+                other
+            )),
+        }
+    }
+}
+
+/// Output format for `Export`: the existing JSON dump, or a representation
+/// of the `sub_ecosystems` graph for visualization/graph-tooling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Json,
+    Dot,
+    Edges,
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(ExportFormat::Json),
+            "dot" => Ok(ExportFormat::Dot),
+            "edges" => Ok(ExportFormat::Edges),
+            other => Err(format!(
+                "invalid format `{}`, expected `json`, `dot`, or `edges`",
+                other
+            )),
+        }
+    }
+}
+
 #[derive(Debug, StructOpt)]
 #[structopt(about = "Taxonomy of crypto open source repositories")]
 #[structopt(name = "crypto-ecosystems", rename_all = "kebab-case")]
@@ -23,24 +72,50 @@ enum Cli {
     Sort {
         /// Path to top level directory containing ecosystem toml files
         data_path: String,
+
+        /// Output format for any errors reported before sorting
+        #[structopt(long, default_value = "human")]
+        format: OutputFormat,
+
+        /// Only report on / rewrite ecosystems whose toml file (or a referenced
+        /// sub-ecosystem) changed since this git ref
+        #[structopt(long)]
+        changed_since: Option<String>,
     },
 
     /// Validate all of the toml configuration files
     Validate {
         /// Path to top level directory containing ecosystem toml files
         data_path: String,
+
+        /// Output format: human-readable prose, or json for CI tooling
+        #[structopt(long, default_value = "human")]
+        format: OutputFormat,
+
+        /// Only report errors for ecosystems whose toml file (or a referenced
+        /// sub-ecosystem) changed since this git ref
+        #[structopt(long)]
+        changed_since: Option<String>,
     },
 
-    /// Export list of ecosystems and repos to a JSON file
+    /// Export the ecosystem hierarchy to a file
     Export {
         /// Path to top level directory containing ecosystem toml files
         data_path: String,
-        /// JSON File to export the list of repos
+        /// File to export the list of repos or the ecosystem graph to
         output_path: String,
 
-        /// Include only repository files
+        /// Include only repository files (json format only)
         #[structopt(short, long)]
         only_repos: bool,
+
+        /// For each ecosystem, include the transitive closure of repos from its sub-ecosystems (json format only)
+        #[structopt(short, long)]
+        expand: bool,
+
+        /// Output format: json, a GraphViz dot digraph, or a tab-separated parent/child edge list
+        #[structopt(long, default_value = "json")]
+        format: ExportFormat,
     },
 }
 
@@ -51,7 +126,8 @@ struct ValidationStats {
     errors: Vec<ValidationError>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
 enum ValidationError {
     MissingSubecosystem { parent: String, child: String },
 
@@ -64,9 +140,11 @@ enum ValidationError {
     UnsortedEcosystem(UnsortedEcosystem),
 
     InvalidRepoUrl { url: String, url_type: RepoUrlType },
+
+    CyclicEcosystem { cycle: Vec<String> },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 struct UnsortedEcosystem {
     ecosystem: String,
     repo_diff: Option<String>,
@@ -121,6 +199,9 @@ impl Display for ValidationError {
                 }
                 _ => Ok(()),
             },
+            ValidationError::CyclicEcosystem { cycle } => {
+                writeln!(f, "Cyclic sub-ecosystem reference: {}", cycle.join(" -> "))
+            }
         }
     }
 }
@@ -150,7 +231,8 @@ enum CEError {
 type EcosystemMap = HashMap<String, Ecosystem>;
 
 /// This enum handles a variety of url types that are put into repo url values.
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
 enum RepoUrlType {
     GithubUnnormalized,
     GithubUserOrOrganization,
@@ -200,26 +282,39 @@ fn get_toml_files(dir: &Path) -> Result<Vec<PathBuf>> {
     Ok(paths)
 }
 
+fn parse_toml_file(toml_path: &PathBuf) -> Result<(String, Ecosystem, Option<ValidationError>)> {
+    let contents = read_to_string(toml_path)?;
+    match toml::from_str::<Ecosystem>(&contents) {
+        Ok(ecosystem) => {
+            let title = ecosystem.title.clone();
+            let title_error = if title.trim() != title {
+                Some(ValidationError::TitleError(toml_path.display().to_string()))
+            } else {
+                None
+            };
+            Ok((title, ecosystem, title_error))
+        }
+        Err(err) => Err(CEError::TomlParseError {
+            path: toml_path.display().to_string(),
+            toml_error: err,
+        }
+        .into()),
+    }
+}
+
 fn parse_toml_files(paths: &[PathBuf]) -> Result<(EcosystemMap, Vec<ValidationError>)> {
+    let parsed: Vec<(String, Ecosystem, Option<ValidationError>)> = paths
+        .par_iter()
+        .map(parse_toml_file)
+        .collect::<Result<Vec<_>>>()?;
+
     let mut ecosystems: HashMap<String, Ecosystem> = HashMap::new();
     let mut errors = Vec::new();
-    for toml_path in paths {
-        let contents = read_to_string(toml_path)?;
-        match toml::from_str::<Ecosystem>(&contents) {
-            Ok(ecosystem) => {
-                let title = ecosystem.title.clone();
-                if title.trim() != title {
-                    errors.push(ValidationError::TitleError(toml_path.display().to_string()));
-                }
-                ecosystems.insert(title, ecosystem);
-            }
-            Err(err) => {
-                Err(CEError::TomlParseError {
-                    path: toml_path.display().to_string(),
-                    toml_error: err,
-                })?;
-            }
+    for (title, ecosystem, title_error) in parsed {
+        if let Some(title_error) = title_error {
+            errors.push(title_error);
         }
+        ecosystems.insert(title, ecosystem);
     }
     Ok((ecosystems, errors))
 }
@@ -245,99 +340,219 @@ fn find_misordered_elements_diff(strings: &[String]) -> Option<String> {
     Some(diff)
 }
 
-fn validate_ecosystems(ecosystem_map: &EcosystemMap) -> ValidationStats {
+struct EcosystemPartial {
+    errors: Vec<ValidationError>,
+    repo_urls: Vec<String>,
+    missing_count: usize,
+    tag_counts: HashMap<String, u32>,
+}
+
+fn validate_one_ecosystem(ecosystem_map: &EcosystemMap, ecosystem: &Ecosystem) -> EcosystemPartial {
     let mut errors = vec![];
-    let mut repo_set = HashSet::new();
-    let mut tagmap: HashMap<String, u32> = HashMap::new();
+    let mut repo_urls = vec![];
+    let mut tag_counts: HashMap<String, u32> = HashMap::new();
     let mut missing_count = 0;
 
-    for ecosystem in ecosystem_map.values() {
-        let has_sub_ecosystems = ecosystem
-            .sub_ecosystems
-            .as_ref()
-            .map_or(false, |sub_ecosystems| !sub_ecosystems.is_empty());
-
-        let has_orgs = ecosystem
-            .github_organizations
-            .as_ref()
-            .map_or(false, |orgs| !orgs.is_empty());
-
-        let has_repos = ecosystem
-            .repo
-            .as_ref()
-            .map_or(false, |repos| !repos.is_empty());
-
-        let mut seen_repos = HashSet::new();
-
-        //let mut sorted_subs = vec![];
-        let mut sort_error = UnsortedEcosystem {
-            ecosystem: ecosystem.title.clone(),
-            repo_diff: None,
-            sub_eco_diff: None,
-            github_org_diff: None,
-        };
-        if let Some(sub_ecosystems) = &ecosystem.sub_ecosystems {
-            for sub in sub_ecosystems {
-                if !ecosystem_map.contains_key(sub) {
-                    errors.push(ValidationError::MissingSubecosystem {
-                        parent: ecosystem.title.clone(),
-                        child: sub.clone(),
-                    });
+    let has_sub_ecosystems = ecosystem
+        .sub_ecosystems
+        .as_ref()
+        .map_or(false, |sub_ecosystems| !sub_ecosystems.is_empty());
+
+    let has_orgs = ecosystem
+        .github_organizations
+        .as_ref()
+        .map_or(false, |orgs| !orgs.is_empty());
+
+    let has_repos = ecosystem
+        .repo
+        .as_ref()
+        .map_or(false, |repos| !repos.is_empty());
+
+    let mut seen_repos = HashSet::new();
+
+    //let mut sorted_subs = vec![];
+    let mut sort_error = UnsortedEcosystem {
+        ecosystem: ecosystem.title.clone(),
+        repo_diff: None,
+        sub_eco_diff: None,
+        github_org_diff: None,
+    };
+    if let Some(sub_ecosystems) = &ecosystem.sub_ecosystems {
+        for sub in sub_ecosystems {
+            if !ecosystem_map.contains_key(sub) {
+                errors.push(ValidationError::MissingSubecosystem {
+                    parent: ecosystem.title.clone(),
+                    child: sub.clone(),
+                });
+            }
+        }
+        sort_error.sub_eco_diff = find_misordered_elements_diff(sub_ecosystems);
+    }
+
+    if let Some(github_orgs) = &ecosystem.github_organizations {
+        sort_error.github_org_diff = find_misordered_elements_diff(github_orgs);
+    }
+
+    if let Some(repos) = &ecosystem.repo {
+        for repo in repos {
+            let lowercase_url = repo.url.to_lowercase();
+            if seen_repos.contains(&lowercase_url) {
+                errors.push(ValidationError::DuplicateRepoUrl(repo.url.clone()));
+            } else {
+                seen_repos.insert(lowercase_url);
+            }
+            if let Some(true) = repo.missing {
+                missing_count += 1;
+            }
+            repo_urls.push(repo.url.clone());
+            if let Some(tags) = &repo.tags {
+                for tag in tags {
+                    let counter = tag_counts.entry(tag.to_string()).or_insert(0);
+                    *counter += 1;
                 }
             }
-            sort_error.sub_eco_diff = find_misordered_elements_diff(sub_ecosystems);
+            let url_type = parse_repo_url_type(&repo.url);
+            match url_type {
+                RepoUrlType::GithubUnnormalized
+                | RepoUrlType::GithubTreeish
+                | RepoUrlType::GithubUserOrOrganization
+                | RepoUrlType::InvalidUrl => errors.push(ValidationError::InvalidRepoUrl {
+                    url: repo.url.clone(),
+                    url_type,
+                }),
+                _ => {}
+            }
         }
+        let sorted_repo_urls: Vec<String> = repos.iter().map(|x| x.url.clone()).collect();
+        sort_error.repo_diff = find_misordered_elements_diff(&sorted_repo_urls);
+    }
 
-        if let Some(github_orgs) = &ecosystem.github_organizations {
-            sort_error.github_org_diff = find_misordered_elements_diff(github_orgs);
+    if !(has_sub_ecosystems || has_orgs || has_repos) {
+        errors.push(ValidationError::EmptyEcosystem(ecosystem.title.clone()));
+    }
+
+    if sort_error.sub_eco_diff.is_some()
+        || sort_error.github_org_diff.is_some()
+        || sort_error.repo_diff.is_some()
+    {
+        errors.push(ValidationError::UnsortedEcosystem(sort_error));
+    }
+
+    EcosystemPartial {
+        errors,
+        repo_urls,
+        missing_count,
+        tag_counts,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DfsColor {
+    White,
+    Gray,
+    Black,
+}
+
+/// Three-color DFS over the `sub_ecosystems` graph. Edges to a missing
+/// sub-ecosystem are skipped; `MissingSubecosystem` already reports those.
+fn detect_cycles(ecosystem_map: &EcosystemMap) -> Vec<ValidationError> {
+    let mut colors: HashMap<String, DfsColor> = ecosystem_map
+        .keys()
+        .map(|title| (title.clone(), DfsColor::White))
+        .collect();
+    let mut errors = vec![];
+
+    for title in ecosystem_map.keys() {
+        if colors[title] == DfsColor::White {
+            let mut stack = vec![];
+            detect_cycles_from(ecosystem_map, title, &mut colors, &mut stack, &mut errors);
         }
+    }
 
-        if let Some(repos) = &ecosystem.repo {
-            for repo in repos {
-                let lowercase_url = repo.url.to_lowercase();
-                if seen_repos.contains(&lowercase_url) {
-                    errors.push(ValidationError::DuplicateRepoUrl(repo.url.clone()));
-                } else {
-                    seen_repos.insert(lowercase_url);
-                }
-                if let Some(true) = repo.missing {
-                    missing_count += 1;
-                }
-                repo_set.insert(repo.url.clone());
-                if let Some(tags) = &repo.tags {
-                    for tag in tags {
-                        let counter = tagmap.entry(tag.to_string()).or_insert(0);
-                        *counter += 1;
-                    }
+    errors
+}
+
+fn detect_cycles_from(
+    ecosystem_map: &EcosystemMap,
+    title: &str,
+    colors: &mut HashMap<String, DfsColor>,
+    stack: &mut Vec<String>,
+    errors: &mut Vec<ValidationError>,
+) {
+    colors.insert(title.to_string(), DfsColor::Gray);
+    stack.push(title.to_string());
+
+    if let Some(sub_ecosystems) = ecosystem_map
+        .get(title)
+        .and_then(|ecosystem| ecosystem.sub_ecosystems.as_ref())
+    {
+        for sub in sub_ecosystems {
+            match colors.get(sub) {
+                None => continue,
+                Some(DfsColor::Black) => continue,
+                Some(DfsColor::Gray) => {
+                    let start = stack.iter().position(|node| node == sub).unwrap_or(0);
+                    let mut cycle = stack[start..].to_vec();
+                    cycle.push(sub.clone());
+                    errors.push(ValidationError::CyclicEcosystem { cycle });
                 }
-                let url_type = parse_repo_url_type(&repo.url);
-                match url_type {
-                    RepoUrlType::GithubUnnormalized
-                    | RepoUrlType::GithubTreeish
-                    | RepoUrlType::GithubUserOrOrganization
-                    | RepoUrlType::InvalidUrl => errors.push(ValidationError::InvalidRepoUrl {
-                        url: repo.url.clone(),
-                        url_type,
-                    }),
-                    _ => {}
+                Some(DfsColor::White) => {
+                    detect_cycles_from(ecosystem_map, sub, colors, stack, errors);
                 }
             }
-            let repo_urls: Vec<String> = repos.iter().map(|x| x.url.clone()).collect();
-            sort_error.repo_diff = find_misordered_elements_diff(&repo_urls);
         }
+    }
 
-        if !(has_sub_ecosystems || has_orgs || has_repos) {
-            errors.push(ValidationError::EmptyEcosystem(ecosystem.title.clone()));
-        }
+    stack.pop();
+    colors.insert(title.to_string(), DfsColor::Black);
+}
 
-        if sort_error.sub_eco_diff.is_some()
-            || sort_error.github_org_diff.is_some()
-            || sort_error.repo_diff.is_some()
-        {
-            errors.push(ValidationError::UnsortedEcosystem(sort_error));
+/// Validates the whole map. Cross-ecosystem checks (missing/cyclic
+/// sub-ecosystems) always require the full tree, but when `surfaced` is
+/// given, only errors belonging to ecosystems in that set are reported --
+/// `repo_count`/`missing_count` still reflect the whole tree. Duplicate repo
+/// URLs are checked within each ecosystem only, not across ecosystems.
+fn validate_ecosystems(
+    ecosystem_map: &EcosystemMap,
+    surfaced: Option<&HashSet<String>>,
+) -> ValidationStats {
+    let partials: Vec<(String, EcosystemPartial)> = ecosystem_map
+        .par_iter()
+        .map(|(title, ecosystem)| {
+            (
+                title.clone(),
+                validate_one_ecosystem(ecosystem_map, ecosystem),
+            )
+        })
+        .collect();
+
+    let mut errors = vec![];
+    let mut repo_set = HashSet::new();
+    let mut tagmap: HashMap<String, u32> = HashMap::new();
+    let mut missing_count = 0;
+
+    for (title, partial) in partials {
+        repo_set.extend(partial.repo_urls);
+        missing_count += partial.missing_count;
+        for (tag, count) in partial.tag_counts {
+            *tagmap.entry(tag).or_insert(0) += count;
+        }
+        if surfaced.map_or(true, |titles| titles.contains(&title)) {
+            errors.extend(partial.errors);
         }
     }
 
+    errors.extend(
+        detect_cycles(ecosystem_map)
+            .into_iter()
+            .filter(|err| match (err, surfaced) {
+                (ValidationError::CyclicEcosystem { cycle }, Some(titles)) => {
+                    cycle.iter().any(|title| titles.contains(title))
+                }
+                _ => true,
+            }),
+    );
+
     ValidationStats {
         ecosystem_count: ecosystem_map.len(),
         repo_count: repo_set.len(),
@@ -346,6 +561,82 @@ fn validate_ecosystems(ecosystem_map: &EcosystemMap) -> ValidationStats {
     }
 }
 
+/// Resolves symlinks/`.`/`..` in `path` so paths built from a possibly
+/// relative CLI argument compare equal to the absolute paths libgit2 hands
+/// back. Falls back to the input unchanged if the path can't be resolved
+/// (e.g. it no longer exists).
+fn canonicalize_best_effort(path: PathBuf) -> PathBuf {
+    std::fs::canonicalize(&path).unwrap_or(path)
+}
+
+/// Diffs the working tree against `gitref` and returns the canonicalized,
+/// absolute paths of every changed `.toml` file (on either side of the diff).
+fn changed_toml_paths(data_path: &Path, gitref: &str) -> Result<HashSet<PathBuf>> {
+    let repo = git2::Repository::discover(data_path)?;
+    let tree = repo.revparse_single(gitref)?.peel_to_tree()?;
+    let mut diff_opts = git2::DiffOptions::new();
+    diff_opts
+        .include_untracked(true)
+        .recurse_untracked_dirs(true);
+    let diff = repo.diff_tree_to_workdir_with_index(Some(&tree), Some(&mut diff_opts))?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("repository has no working directory"))?
+        .to_path_buf();
+
+    let mut changed = HashSet::new();
+    diff.foreach(
+        &mut |delta, _| {
+            for file in [delta.old_file(), delta.new_file()] {
+                if let Some(path) = file.path() {
+                    if path.extension().map_or(false, |ext| ext == "toml") {
+                        changed.insert(canonicalize_best_effort(workdir.join(path)));
+                    }
+                }
+            }
+            true
+        },
+        None,
+        None,
+        None,
+    )?;
+    Ok(changed)
+}
+
+/// Ecosystems surfaced by `--changed-since`: those whose own file changed,
+/// plus any ecosystem that directly lists one of those as a sub-ecosystem.
+/// `data_path` and `changed_paths` may have come from different sources (a
+/// raw, possibly relative CLI argument vs. libgit2's absolute workdir), so
+/// both sides are canonicalized before comparison.
+fn surfaced_ecosystems(
+    ecosystem_map: &EcosystemMap,
+    data_path: &Path,
+    changed_paths: &HashSet<PathBuf>,
+) -> HashSet<String> {
+    let data_path = canonicalize_best_effort(data_path.to_path_buf());
+    let directly_changed: HashSet<String> = ecosystem_map
+        .values()
+        .filter(|ecosystem| {
+            let eco_path = canonicalize_best_effort(canonical_path(&data_path, &ecosystem.title));
+            changed_paths.contains(&eco_path)
+        })
+        .map(|ecosystem| ecosystem.title.clone())
+        .collect();
+
+    let mut surfaced = directly_changed.clone();
+    for ecosystem in ecosystem_map.values() {
+        let references_changed = ecosystem
+            .sub_ecosystems
+            .iter()
+            .flatten()
+            .any(|sub| directly_changed.contains(sub));
+        if references_changed {
+            surfaced.insert(ecosystem.title.clone());
+        }
+    }
+    surfaced
+}
+
 fn canonical_path(repo_root: &Path, eco_title: &str) -> PathBuf {
     let slug = slugify(eco_title);
     if slug.is_empty() {
@@ -445,21 +736,68 @@ fn write_ecosystem_to_toml(repo_root: &Path, eco: &Ecosystem) -> Result<()> {
     Ok(())
 }
 
-fn validate(data_path: String) -> Result<()> {
+#[derive(Debug, Serialize)]
+struct ValidationSummary {
+    ecosystem_count: usize,
+    repo_count: usize,
+    missing_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ValidationReport {
+    errors: Vec<ValidationError>,
+    summary: ValidationSummary,
+}
+
+fn validate(data_path: String, format: OutputFormat, changed_since: Option<String>) -> Result<()> {
     let toml_files = get_toml_files(Path::new(&data_path))?;
     match parse_toml_files(&toml_files) {
         Ok((ecosystem_map, title_errors)) => {
-            let mut stats = validate_ecosystems(&ecosystem_map);
-            stats.errors.extend(title_errors);
-            if stats.errors.is_empty() {
-                println!(
-                    "Validated {} ecosystems and {} repos ({} missing)",
-                    stats.ecosystem_count, stats.repo_count, stats.missing_count,
-                );
-            } else {
-                for err in stats.errors {
-                    print!("{}", err);
+            let data_dir = Path::new(&data_path);
+            let changed_paths = changed_since
+                .as_deref()
+                .map(|gitref| changed_toml_paths(data_dir, gitref))
+                .transpose()?;
+            let surfaced = changed_paths
+                .as_ref()
+                .map(|paths| surfaced_ecosystems(&ecosystem_map, data_dir, paths));
+
+            let mut stats = validate_ecosystems(&ecosystem_map, surfaced.as_ref());
+            stats.errors.extend(title_errors.into_iter().filter(|err| {
+                match (err, &changed_paths) {
+                    (ValidationError::TitleError(file), Some(paths)) => {
+                        paths.contains(&canonicalize_best_effort(PathBuf::from(file)))
+                    }
+                    _ => true,
+                }
+            }));
+            let has_errors = !stats.errors.is_empty();
+            match format {
+                OutputFormat::Human => {
+                    if has_errors {
+                        for err in &stats.errors {
+                            print!("{}", err);
+                        }
+                    } else {
+                        println!(
+                            "Validated {} ecosystems and {} repos ({} missing)",
+                            stats.ecosystem_count, stats.repo_count, stats.missing_count,
+                        );
+                    }
                 }
+                OutputFormat::Json => {
+                    let report = ValidationReport {
+                        summary: ValidationSummary {
+                            ecosystem_count: stats.ecosystem_count,
+                            repo_count: stats.repo_count,
+                            missing_count: stats.missing_count,
+                        },
+                        errors: stats.errors,
+                    };
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                }
+            }
+            if has_errors {
                 std::process::exit(-1);
             }
         }
@@ -471,11 +809,162 @@ fn validate(data_path: String) -> Result<()> {
     Ok(())
 }
 
-fn export(data_path: String, output_path: String, only_repos: bool) -> Result<()> {
+/// Computes the transitive closure of repo URLs reachable from `title` by
+/// following `sub_ecosystems`, de-duplicating case-insensitively on URL
+/// (consistent with the `seen_repos` logic in `validate_one_ecosystem`).
+/// `memo` caches completed results, so a sub-ecosystem shared by several
+/// parents (a diamond) is expanded once; `visiting` holds the current
+/// recursion path and guards against cycles.
+///
+/// Callers are expected to run cycle detection (`detect_cycles`) first --
+/// `export` aborts before this runs if any cycle is found. If a cycle slips
+/// through anyway, a node on it reports a truncated result (see
+/// `expand_ecosystem_repos_inner`) and that result is never memoized, so the
+/// cache can't be poisoned by HashMap iteration order.
+fn expand_ecosystem_repos(
+    ecosystem_map: &EcosystemMap,
+    title: &str,
+    memo: &mut HashMap<String, Vec<String>>,
+    visiting: &mut HashSet<String>,
+) -> Vec<String> {
+    expand_ecosystem_repos_inner(ecosystem_map, title, memo, visiting).0
+}
+
+/// Returns `(repos, truncated)`, where `truncated` is `true` if this call --
+/// or any sub-ecosystem it recursed into -- hit a node still on the
+/// recursion stack (i.e. a cycle). Truncated results must not be memoized:
+/// they're missing repos that only the in-progress ancestor call could add.
+fn expand_ecosystem_repos_inner(
+    ecosystem_map: &EcosystemMap,
+    title: &str,
+    memo: &mut HashMap<String, Vec<String>>,
+    visiting: &mut HashSet<String>,
+) -> (Vec<String>, bool) {
+    if let Some(cached) = memo.get(title) {
+        return (cached.clone(), false);
+    }
+    if visiting.contains(title) {
+        return (vec![], true);
+    }
+    visiting.insert(title.to_string());
+
+    let mut seen = HashSet::new();
+    let mut repos = vec![];
+    let mut truncated = false;
+    if let Some(ecosystem) = ecosystem_map.get(title) {
+        for repo in ecosystem.repo.iter().flatten() {
+            if seen.insert(repo.url.to_lowercase()) {
+                repos.push(repo.url.clone());
+            }
+        }
+        for sub in ecosystem.sub_ecosystems.iter().flatten() {
+            if !ecosystem_map.contains_key(sub) {
+                continue;
+            }
+            let (sub_repos, sub_truncated) =
+                expand_ecosystem_repos_inner(ecosystem_map, sub, memo, visiting);
+            truncated |= sub_truncated;
+            for url in sub_repos {
+                if seen.insert(url.to_lowercase()) {
+                    repos.push(url);
+                }
+            }
+        }
+    }
+
+    visiting.remove(title);
+    if !truncated {
+        memo.insert(title.to_string(), repos.clone());
+    }
+    (repos, truncated)
+}
+
+/// Renders the `sub_ecosystems` graph as a GraphViz `digraph`: one node per
+/// ecosystem (labeled with its direct repo count), one edge per
+/// parent->sub-ecosystem relationship. Sub-ecosystems with no matching
+/// ecosystem are rendered as dashed, red nodes so gaps are visible at a
+/// glance. Node IDs are slugified titles to keep them DOT-safe.
+fn render_ecosystem_dot(ecosystem_map: &EcosystemMap) -> String {
+    let mut dot = String::from("digraph ecosystems {\n");
+
+    for ecosystem in ecosystem_map.values() {
+        let repo_count = ecosystem.repo.iter().flatten().count();
+        dot.push_str(&format!(
+            "  \"{}\" [label=\"{} ({} repos)\"];\n",
+            slugify(&ecosystem.title),
+            ecosystem.title.replace('"', "\\\""),
+            repo_count,
+        ));
+    }
+
+    let mut missing_subs = HashSet::new();
+    for ecosystem in ecosystem_map.values() {
+        for sub in ecosystem.sub_ecosystems.iter().flatten() {
+            if !ecosystem_map.contains_key(sub) && missing_subs.insert(sub) {
+                dot.push_str(&format!(
+                    "  \"{}\" [label=\"{}\", style=dashed, color=red];\n",
+                    slugify(sub),
+                    sub.replace('"', "\\\""),
+                ));
+            }
+        }
+    }
+
+    for ecosystem in ecosystem_map.values() {
+        for sub in ecosystem.sub_ecosystems.iter().flatten() {
+            dot.push_str(&format!(
+                "  \"{}\" -> \"{}\";\n",
+                slugify(&ecosystem.title),
+                slugify(sub),
+            ));
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Renders the `sub_ecosystems` graph as a two-column `parent\tchild` edge
+/// list, suitable for loading into graph-analysis libraries.
+fn render_ecosystem_edges(ecosystem_map: &EcosystemMap) -> String {
+    let mut edges = String::new();
+    for ecosystem in ecosystem_map.values() {
+        for sub in ecosystem.sub_ecosystems.iter().flatten() {
+            edges.push_str(&format!("{}\t{}\n", ecosystem.title, sub));
+        }
+    }
+    edges
+}
+
+fn export(
+    data_path: String,
+    output_path: String,
+    only_repos: bool,
+    expand: bool,
+    format: ExportFormat,
+) -> Result<()> {
     let toml_files = get_toml_files(Path::new(&data_path))?;
     match parse_toml_files(&toml_files) {
         Ok((ecosystem_map, title_errors)) => {
-            let mut stats = validate_ecosystems(&ecosystem_map);
+            // The graph formats render the raw sub_ecosystems structure,
+            // including gaps (missing sub-ecosystems, cycles) that the
+            // validation gate below would otherwise abort on -- seeing those
+            // gaps in the rendered graph is the point of `--format dot`.
+            match format {
+                ExportFormat::Dot => {
+                    let mut file = File::create(output_path)?;
+                    file.write_all(render_ecosystem_dot(&ecosystem_map).as_bytes())?;
+                    return Ok(());
+                }
+                ExportFormat::Edges => {
+                    let mut file = File::create(output_path)?;
+                    file.write_all(render_ecosystem_edges(&ecosystem_map).as_bytes())?;
+                    return Ok(());
+                }
+                ExportFormat::Json => {}
+            }
+
+            let mut stats = validate_ecosystems(&ecosystem_map, None);
             stats.errors.extend(title_errors);
             if !stats.errors.is_empty() {
                 for err in stats.errors {
@@ -483,6 +972,24 @@ fn export(data_path: String, output_path: String, only_repos: bool) -> Result<()
                 }
                 std::process::exit(-1);
             }
+            if expand {
+                let mut memo: HashMap<String, Vec<String>> = HashMap::new();
+                let mut visiting = HashSet::new();
+                let expanded: HashMap<&String, Vec<String>> = ecosystem_map
+                    .values()
+                    .map(|ecosystem| {
+                        let repos = expand_ecosystem_repos(
+                            &ecosystem_map,
+                            &ecosystem.title,
+                            &mut memo,
+                            &mut visiting,
+                        );
+                        (&ecosystem.title, repos)
+                    })
+                    .collect();
+                serde_json::to_writer_pretty(File::create(output_path)?, &expanded)?;
+                return Ok(());
+            }
             if only_repos {
                 let mut repo_set: HashMap<&String, Vec<String>> = HashMap::new();
                 for ecosystem in ecosystem_map.values() {
@@ -508,20 +1015,45 @@ fn export(data_path: String, output_path: String, only_repos: bool) -> Result<()
     Ok(())
 }
 
-fn sort(data_path_str: &str) -> Result<()> {
+fn sort(data_path_str: &str, format: OutputFormat, changed_since: Option<String>) -> Result<()> {
     let data_path = Path::new(data_path_str);
     let toml_files = get_toml_files(data_path)?;
     match parse_toml_files(&toml_files) {
         Ok((ecosystem_map, title_errors)) => {
             let mut unsorted_count = 0;
+            let changed_paths = changed_since
+                .as_deref()
+                .map(|gitref| changed_toml_paths(data_path, gitref))
+                .transpose()?;
+            let surfaced = changed_paths
+                .as_ref()
+                .map(|paths| surfaced_ecosystems(&ecosystem_map, data_path, paths));
+
+            let title_errors: Vec<ValidationError> = title_errors
+                .into_iter()
+                .filter(|err| match (err, &changed_paths) {
+                    (ValidationError::TitleError(file), Some(paths)) => {
+                        paths.contains(&canonicalize_best_effort(PathBuf::from(file)))
+                    }
+                    _ => true,
+                })
+                .collect();
+
             if !title_errors.is_empty() {
-                println!("Please fix the following errors before sorting");
-                for err in title_errors {
-                    print!("\t{}", err);
+                match format {
+                    OutputFormat::Human => {
+                        println!("Please fix the following errors before sorting");
+                        for err in &title_errors {
+                            print!("\t{}", err);
+                        }
+                    }
+                    OutputFormat::Json => {
+                        println!("{}", serde_json::to_string_pretty(&title_errors)?);
+                    }
                 }
                 std::process::exit(-1);
             }
-            let stats = validate_ecosystems(&ecosystem_map);
+            let stats = validate_ecosystems(&ecosystem_map, surfaced.as_ref());
             for error in stats.errors {
                 if let ValidationError::UnsortedEcosystem(unsorted_eco) = error {
                     println!("Sorting Ecosystem: {}", unsorted_eco.ecosystem);
@@ -546,17 +1078,156 @@ fn sort(data_path_str: &str) -> Result<()> {
 fn main() -> Result<()> {
     let args = Cli::from_args();
     match args {
-        Cli::Sort { data_path } => {
-            sort(&data_path)?;
+        Cli::Sort {
+            data_path,
+            format,
+            changed_since,
+        } => {
+            sort(&data_path, format, changed_since)?;
         }
-        Cli::Validate { data_path } => {
-            validate(data_path)?;
+        Cli::Validate {
+            data_path,
+            format,
+            changed_since,
+        } => {
+            validate(data_path, format, changed_since)?;
         }
         Cli::Export {
             data_path,
             output_path,
             only_repos,
-        } => export(data_path, output_path, only_repos)?,
+            expand,
+            format,
+        } => export(data_path, output_path, only_repos, expand, format)?,
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Restores the process's working directory on drop, even if the test
+    /// panics, since `set_current_dir` is global process state.
+    struct CwdGuard(PathBuf);
+
+    impl CwdGuard {
+        fn enter(dir: &Path) -> Self {
+            let previous = std::env::current_dir().unwrap();
+            std::env::set_current_dir(dir).unwrap();
+            CwdGuard(previous)
+        }
+    }
+
+    impl Drop for CwdGuard {
+        fn drop(&mut self) {
+            let _ = std::env::set_current_dir(&self.0);
+        }
+    }
+
+    fn unique_temp_dir() -> PathBuf {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let mut dir = std::env::temp_dir();
+        dir.push(format!(
+            "crypto-ecosystems-test-{}-{}",
+            std::process::id(),
+            n
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_eco_file(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    fn commit_all(repo: &git2::Repository, message: &str) {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+            .unwrap();
+    }
+
+    #[test]
+    fn changed_since_surfaces_files_under_a_relative_data_path() {
+        let dir = unique_temp_dir();
+        let repo = git2::Repository::init(&dir).unwrap();
+
+        let alpha_path = canonical_path(&dir, "Alpha");
+        write_eco_file(
+            &alpha_path,
+            "title = \"Alpha\"\n\n[[repo]]\nurl = \"https://github.com/alpha/alpha\"\n",
+        );
+        commit_all(&repo, "add alpha");
+
+        // A second, untouched ecosystem -- it must NOT be surfaced.
+        let beta_path = canonical_path(&dir, "Beta");
+        write_eco_file(
+            &beta_path,
+            "title = \"Beta\"\n\n[[repo]]\nurl = \"https://github.com/beta/beta\"\n",
+        );
+        commit_all(&repo, "add beta");
+
+        // Change alpha after the commit, so it's dirty relative to HEAD.
+        write_eco_file(
+            &alpha_path,
+            "title = \"Alpha\"\n\n[[repo]]\nurl = \"https://github.com/alpha/alpha\"\n[[repo]]\nurl = \"https://github.com/alpha/new-repo\"\n",
+        );
+
+        // Enter the repo and use `.` as the data_path, as a real CLI
+        // invocation like `validate . --changed-since HEAD` would.
+        let _cwd = CwdGuard::enter(&dir);
+        let relative_data_path = Path::new(".");
+
+        let changed_paths = changed_toml_paths(relative_data_path, "HEAD").unwrap();
+        assert!(
+            !changed_paths.is_empty(),
+            "expected the modified alpha.toml to be detected as changed"
+        );
+
+        let mut ecosystem_map = EcosystemMap::new();
+        ecosystem_map.insert(
+            "Alpha".to_string(),
+            Ecosystem {
+                title: "Alpha".to_string(),
+                github_organizations: None,
+                sub_ecosystems: None,
+                repo: None,
+            },
+        );
+        ecosystem_map.insert(
+            "Beta".to_string(),
+            Ecosystem {
+                title: "Beta".to_string(),
+                github_organizations: None,
+                sub_ecosystems: None,
+                repo: None,
+            },
+        );
+
+        let surfaced = surfaced_ecosystems(&ecosystem_map, relative_data_path, &changed_paths);
+        assert!(
+            surfaced.contains("Alpha"),
+            "Alpha's changed file should be surfaced even with a relative data_path"
+        );
+        assert!(
+            !surfaced.contains("Beta"),
+            "Beta was never touched and should not be surfaced"
+        );
+    }
+}